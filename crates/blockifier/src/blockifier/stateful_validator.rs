@@ -21,7 +21,9 @@ use crate::state::errors::StateError;
 use crate::state::state_api::{State, StateReader};
 use crate::transaction::account_transaction::AccountTransaction;
 use crate::transaction::errors::{TransactionExecutionError, TransactionPreValidationError};
-use crate::transaction::objects::{TransactionExecutionResult, TransactionInfo};
+use crate::transaction::objects::{
+    TransactionExecutionInfo, TransactionExecutionResult, TransactionInfo,
+};
 use crate::transaction::transaction_execution::Transaction;
 use crate::transaction::transactions::ValidatableTransaction;
 
@@ -39,14 +41,52 @@ pub enum StatefulValidatorError {
     TransactionExecutorError(#[from] TransactionExecutorError),
     #[error(transparent)]
     TransactionPreValidationError(#[from] TransactionPreValidationError),
+    #[error(
+        "Transaction nonce {tx_nonce:?} is ahead of the account nonce {account_nonce:?} by more \
+         than the allowed gap of {allow_future_nonce_gap}."
+    )]
+    NonceTooFarInFuture { tx_nonce: Nonce, account_nonce: Nonce, allow_future_nonce_gap: u64 },
 }
 
 pub type StatefulValidatorResult<T> = Result<T, StatefulValidatorError>;
 
+/// The outcome of a successful [`StatefulValidator::perform_validations`] call. Carries the data
+/// already produced while validating, so that a mempool can order and bound transactions (by
+/// fee-per-gas, by per-sender resource caps, ...) without re-running validation to get it.
+#[derive(Debug)]
+pub struct StatefulValidationReport {
+    /// Whether the transaction is ready to be sequenced or only queued behind a nonce gap.
+    pub validation_status: ValidationStatus,
+    /// The estimated fee and resources consumed by the transaction. Only meaningful when
+    /// `validate_call_info` is `Some`: whenever `__validate__` was skipped (explicitly via
+    /// `skip_validate`, or because the account's deploy is still unprocessed), this is
+    /// `TransactionReceipt::default()`, not an actual zero-fee estimate.
+    pub tx_receipt: TransactionReceipt,
+    /// The `__validate__` call info, or `None` if validation was skipped.
+    pub validate_call_info: Option<CallInfo>,
+    /// The sender's nonce following this call.
+    pub sender_nonce: Nonce,
+}
+
+/// Distinguishes a transaction that is next-in-line for its sender from one that is only
+/// queued behind a nonce gap, so that a mempool can hold the latter until the gap closes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ValidationStatus {
+    /// The transaction's nonce is the sender's current nonce; it may be executed immediately.
+    Ready,
+    /// The transaction's nonce is ahead of the sender's current nonce (within the configured
+    /// gap); it must wait for its predecessors to be processed first.
+    Queued,
+}
+
 /// Manages state related transaction validations for pre-execution flows.
 pub struct StatefulValidator<S: StateReader> {
     pub tx_executor: TransactionExecutor<S>,
     max_nonce_for_validation_skip: Nonce,
+    /// The maximal nonce gap, relative to the sender's current on-chain nonce, for which a
+    /// transaction is still accepted (and reported as [`ValidationStatus::Queued`]) instead of
+    /// being rejected outright. Lets a mempool queue a chain of future-nonce transactions.
+    allow_future_nonce_gap: u64,
 }
 
 impl<S: StateReader> StatefulValidator<S> {
@@ -54,10 +94,11 @@ impl<S: StateReader> StatefulValidator<S> {
         state: CachedState<S>,
         block_context: BlockContext,
         max_nonce_for_validation_skip: Nonce,
+        allow_future_nonce_gap: u64,
     ) -> Self {
         let tx_executor =
             TransactionExecutor::new(state, block_context, TransactionExecutorConfig::default());
-        Self { tx_executor, max_nonce_for_validation_skip }
+        Self { tx_executor, max_nonce_for_validation_skip, allow_future_nonce_gap }
     }
 
     /// Perform validations on an account transaction.
@@ -65,9 +106,20 @@ impl<S: StateReader> StatefulValidator<S> {
     /// # Arguments
     ///
     /// * `tx` - The account transaction to validate.
+    /// * `deploy_account_tx_hash` - If the account's `DeployAccount` is still sitting
+    ///   unprocessed (e.g. in a mempool), its hash. Used to let subsequent transactions from the
+    ///   same account skip `__validate__` until the deploy is processed.
     /// * `skip_validate` - If true, skip the account validation.
     /// * `skip_fee_check` - If true, ignore any fee related checks on the transaction and account
     ///   balance.
+    /// * `strict_nonce_check` - If true, require the transaction's nonce to exactly match the
+    ///   sender's current nonce. If false, a transaction whose nonce is ahead of the sender's
+    ///   current nonce (within `allow_future_nonce_gap`) is accepted and reported as
+    ///   [`ValidationStatus::Queued`] instead of being rejected.
+    ///
+    /// Returns a [`StatefulValidationReport`] carrying the estimated fee/resources, the
+    /// `__validate__` call info (if run), and the sender's resulting nonce, so a mempool can
+    /// rank and re-validate the transaction without repeating validation.
     ///
     /// NOTE:
     ///
@@ -78,36 +130,73 @@ impl<S: StateReader> StatefulValidator<S> {
     pub fn perform_validations(
         &mut self,
         tx: AccountTransaction,
+        deploy_account_tx_hash: Option<TransactionHash>,
         skip_validate: bool,
         skip_fee_check: bool,
-    ) -> StatefulValidatorResult<()> {
+        strict_nonce_check: bool,
+    ) -> StatefulValidatorResult<StatefulValidationReport> {
+        // Computed up front (before `tx` is potentially moved into `self.execute`) since it's
+        // needed by every path below.
+        let tx_context = self.tx_executor.block_context.to_tx_context(&tx);
+        let sender_address = tx_context.tx_info.sender_address();
+
         // Deploy account transactions should be fully executed, since the constructor must run
         // before `__validate_deploy__`. The execution already includes all necessary validations,
         // so they are skipped here.
         if let AccountTransaction::DeployAccount(_) = tx {
-            self.execute(tx)?;
-            return Ok(());
+            let tx_execution_info = self.execute(tx)?;
+            return Ok(StatefulValidationReport {
+                validation_status: ValidationStatus::Ready,
+                tx_receipt: tx_execution_info.receipt,
+                validate_call_info: tx_execution_info.validate_call_info,
+                sender_nonce: self.get_nonce(sender_address)?,
+            });
         }
 
         // First, we check if the transaction should be skipped due to the deploy account not being
         // processed. It is done before the pre-validations checks because, in these checks, we
         // change the state (more precisely, we increment the nonce).
-        let tx_context = self.tx_executor.block_context.to_tx_context(&tx);
-        // let skip_validate = self.skip_validate_due_to_unprocessed_deploy_account(
-        //     &tx_context.tx_info,
-        //     deploy_account_tx_hash,
-        // )?;
-        self.perform_pre_validation_stage(&tx, &tx_context, !skip_fee_check)?;
+        let skip_validate = skip_validate
+            || self.skip_validate_due_to_unprocessed_deploy_account(
+                &tx_context.tx_info,
+                deploy_account_tx_hash,
+            )?;
+
+        // A transaction whose nonce is ahead of the sender's current nonce is only queued: its
+        // predecessors haven't run yet, so we must not treat it as the sender's next transaction.
+        // Anything beyond `allow_future_nonce_gap` is rejected outright rather than misreported
+        // as ready, since it cannot legally execute next and a mempool has no use for it yet.
+        let current_nonce = self.get_nonce(sender_address)?;
+        let tx_nonce = tx_context.tx_info.nonce();
+        let max_queued_nonce = Nonce(current_nonce.0 + Felt::from(self.allow_future_nonce_gap));
+        if tx_nonce > max_queued_nonce {
+            return Err(StatefulValidatorError::NonceTooFarInFuture {
+                tx_nonce,
+                account_nonce: current_nonce,
+                allow_future_nonce_gap: self.allow_future_nonce_gap,
+            });
+        }
+        let validation_status = if tx_nonce > current_nonce {
+            ValidationStatus::Queued
+        } else {
+            ValidationStatus::Ready
+        };
 
-        if !skip_validate {
+        self.perform_pre_validation_stage(&tx, &tx_context, skip_fee_check, strict_nonce_check)?;
+
+        let (validate_call_info, tx_receipt) = if !skip_validate {
             // `__validate__` call.
             let versioned_constants = &tx_context.block_context.versioned_constants();
-            let (_optional_call_info, actual_cost) =
+            let (validate_call_info, tx_receipt) =
                 self.validate(&tx, versioned_constants.tx_initial_gas())?;
 
             // Post validations.
-            PostValidationReport::verify(&tx_context, &actual_cost)?;
-        }
+            PostValidationReport::verify(&tx_context, &tx_receipt)?;
+
+            (validate_call_info, tx_receipt)
+        } else {
+            (None, TransactionReceipt::default())
+        };
 
         // See similar comment in `run_revertible` for context.
         //
@@ -116,27 +205,43 @@ impl<S: StateReader> StatefulValidator<S> {
         // this is manually placed here.
         //
         // TODO: find a better place to put this without needing this duplication.
-        self.tx_executor
-            .block_state
-            .as_mut()
-            .expect(BLOCK_STATE_ACCESS_ERR)
-            .increment_nonce(tx_context.tx_info.sender_address())?;
+        //
+        // Only a sequentially-next (ready) transaction actually advances the sender's nonce;
+        // queued transactions are held until the gap closes and must not bump it prematurely.
+        if validation_status == ValidationStatus::Ready {
+            self.tx_executor
+                .block_state
+                .as_mut()
+                .expect(BLOCK_STATE_ACCESS_ERR)
+                .increment_nonce(sender_address)?;
+        }
 
-        Ok(())
+        Ok(StatefulValidationReport {
+            validation_status,
+            tx_receipt,
+            validate_call_info,
+            sender_nonce: self.get_nonce(sender_address)?,
+        })
     }
 
-    fn execute(&mut self, tx: AccountTransaction) -> StatefulValidatorResult<()> {
-        self.tx_executor.execute(&Transaction::AccountTransaction(tx))?;
-        Ok(())
+    fn execute(
+        &mut self,
+        tx: AccountTransaction,
+    ) -> StatefulValidatorResult<TransactionExecutionInfo> {
+        Ok(self.tx_executor.execute(&Transaction::AccountTransaction(tx))?)
     }
 
     fn perform_pre_validation_stage(
         &mut self,
         tx: &AccountTransaction,
         tx_context: &TransactionContext,
-        fee_check: bool,
+        skip_fee_check: bool,
+        strict_nonce_check: bool,
     ) -> StatefulValidatorResult<()> {
-        let strict_nonce_check = false;
+        // Transactions that declare no fee (e.g. zero resource bounds / zero max fee) can never
+        // satisfy a balance check, so only enforce it when the transaction itself calls for a fee
+        // and the caller hasn't explicitly disabled fee checks (Katana's fee-disabled mode).
+        let fee_check = !skip_fee_check && tx_context.tx_info.enforce_fee();
         // Run pre-validation in charge fee mode to perform fee and balance related checks.
         tx.perform_pre_validation_stage(
             self.tx_executor.block_state.as_mut().expect(BLOCK_STATE_ACCESS_ERR),
@@ -222,4 +327,18 @@ impl<S: StateReader> StatefulValidator<S> {
             .expect(BLOCK_STATE_ACCESS_ERR)
             .get_nonce_at(account_address)?)
     }
+
+    /// Discards any nonce increments and other state mutations accumulated by previous
+    /// `perform_validations` calls, while keeping the (expensive to build) `BlockContext` and the
+    /// underlying state reader intact.
+    ///
+    /// This lets a sequencer validate many competing candidate transactions against the same
+    /// committed state cheaply and in isolation, instead of tearing down and reconstructing the
+    /// whole `TransactionExecutor` (and its `BlockContext`) per transaction.
+    pub fn reset(&mut self) -> StatefulValidatorResult<()> {
+        let block_state = self.tx_executor.block_state.take().expect(BLOCK_STATE_ACCESS_ERR);
+        self.tx_executor.block_state = Some(CachedState::new(block_state.state));
+
+        Ok(())
+    }
 }
@@ -0,0 +1,192 @@
+use assert_matches::assert_matches;
+use rstest::{fixture, rstest};
+use starknet_api::core::{ContractAddress, Nonce};
+use starknet_api::felt;
+use starknet_api::transaction::{Fee, TransactionHash, TransactionVersion};
+
+use crate::blockifier::stateful_validator::{
+    StatefulValidator, StatefulValidatorError, ValidationStatus,
+};
+use crate::context::BlockContext;
+use crate::invoke_tx_args;
+use crate::state::state_api::StateReader;
+use crate::test_utils::contracts::FeatureContract;
+use crate::test_utils::initial_test_state::test_state;
+use crate::test_utils::{CairoVersion, BALANCE, MAX_FEE};
+use crate::transaction::account_transaction::AccountTransaction;
+use crate::transaction::test_utils::account_invoke_tx;
+
+const MAX_NONCE_FOR_VALIDATION_SKIP: u64 = 0;
+const ALLOW_FUTURE_NONCE_GAP: u64 = 2;
+
+fn account_contract() -> FeatureContract {
+    FeatureContract::AccountWithoutValidations(CairoVersion::Cairo1)
+}
+
+#[fixture]
+fn block_context() -> BlockContext {
+    BlockContext::create_for_testing()
+}
+
+#[fixture]
+fn validator(block_context: BlockContext) -> StatefulValidator<impl StateReader> {
+    let state = test_state(&block_context.chain_info, BALANCE, &[(account_contract(), 1)]);
+    StatefulValidator::create(
+        state,
+        block_context,
+        Nonce(felt!(MAX_NONCE_FOR_VALIDATION_SKIP)),
+        ALLOW_FUTURE_NONCE_GAP,
+    )
+}
+
+fn invoke_tx_with_nonce(sender_address: ContractAddress, nonce: Nonce) -> AccountTransaction {
+    account_invoke_tx(invoke_tx_args! { sender_address, nonce })
+}
+
+#[rstest]
+fn perform_validations_with_current_nonce_is_ready(
+    mut validator: StatefulValidator<impl StateReader>,
+) {
+    let sender_address = account_contract().get_instance_address(0);
+    let current_nonce = validator.get_nonce(sender_address).unwrap();
+    let tx = invoke_tx_with_nonce(sender_address, current_nonce);
+
+    let report = validator.perform_validations(tx, None, false, false, false).unwrap();
+
+    assert_eq!(report.validation_status, ValidationStatus::Ready);
+    assert_eq!(report.sender_nonce, Nonce(current_nonce.0 + felt!(1_u8)));
+}
+
+#[rstest]
+fn perform_validations_within_gap_is_queued_and_does_not_bump_nonce(
+    mut validator: StatefulValidator<impl StateReader>,
+) {
+    let sender_address = account_contract().get_instance_address(0);
+    let current_nonce = validator.get_nonce(sender_address).unwrap();
+    let future_nonce = Nonce(current_nonce.0 + felt!(ALLOW_FUTURE_NONCE_GAP));
+    let tx = invoke_tx_with_nonce(sender_address, future_nonce);
+
+    let report = validator.perform_validations(tx, None, false, false, false).unwrap();
+
+    assert_eq!(report.validation_status, ValidationStatus::Queued);
+    // The nonce must not advance: this tx's predecessors haven't executed yet.
+    assert_eq!(report.sender_nonce, current_nonce);
+}
+
+#[rstest]
+fn perform_validations_beyond_gap_is_rejected(mut validator: StatefulValidator<impl StateReader>) {
+    let sender_address = account_contract().get_instance_address(0);
+    let current_nonce = validator.get_nonce(sender_address).unwrap();
+    let too_far_nonce = Nonce(current_nonce.0 + felt!(ALLOW_FUTURE_NONCE_GAP) + felt!(1_u8));
+    let tx = invoke_tx_with_nonce(sender_address, too_far_nonce);
+
+    let error = validator.perform_validations(tx, None, false, false, false).unwrap_err();
+
+    assert_matches!(error, StatefulValidatorError::NonceTooFarInFuture { .. });
+    // The rejected tx must not have mutated the sender's nonce.
+    assert_eq!(validator.get_nonce(sender_address).unwrap(), current_nonce);
+}
+
+#[rstest]
+fn skip_validate_due_to_unprocessed_deploy_account_skips_validate(block_context: BlockContext) {
+    let max_nonce_for_validation_skip = Nonce(felt!(2_u8));
+    let state = test_state(&block_context.chain_info, BALANCE, &[(account_contract(), 1)]);
+    let mut validator = StatefulValidator::create(
+        state,
+        block_context,
+        max_nonce_for_validation_skip,
+        ALLOW_FUTURE_NONCE_GAP,
+    );
+    let sender_address = account_contract().get_instance_address(0);
+    // Post-deploy nonce, within `max_nonce_for_validation_skip`.
+    let tx = invoke_tx_with_nonce(sender_address, Nonce(felt!(1_u8)));
+
+    let report = validator
+        .perform_validations(tx, Some(TransactionHash::default()), false, false, false)
+        .unwrap();
+
+    assert_eq!(report.validate_call_info, None);
+}
+
+#[rstest]
+fn skip_validate_due_to_unprocessed_deploy_account_respects_nonce_boundary(
+    block_context: BlockContext,
+) {
+    let max_nonce_for_validation_skip = Nonce(felt!(1_u8));
+    let state = test_state(&block_context.chain_info, BALANCE, &[(account_contract(), 1)]);
+    let mut validator = StatefulValidator::create(
+        state,
+        block_context,
+        max_nonce_for_validation_skip,
+        ALLOW_FUTURE_NONCE_GAP,
+    );
+    let sender_address = account_contract().get_instance_address(0);
+    // Beyond `max_nonce_for_validation_skip`: must not be skipped.
+    let tx = invoke_tx_with_nonce(sender_address, Nonce(felt!(2_u8)));
+
+    let report = validator
+        .perform_validations(tx, Some(TransactionHash::default()), false, false, false)
+        .unwrap();
+
+    assert!(report.validate_call_info.is_some());
+}
+
+#[rstest]
+fn reset_discards_nonce_increments_but_keeps_the_same_reader(
+    mut validator: StatefulValidator<impl StateReader>,
+) {
+    let sender_address = account_contract().get_instance_address(0);
+    let initial_nonce = validator.get_nonce(sender_address).unwrap();
+    let tx = invoke_tx_with_nonce(sender_address, initial_nonce);
+    validator.perform_validations(tx, None, false, false, false).unwrap();
+    assert_eq!(validator.get_nonce(sender_address).unwrap(), Nonce(initial_nonce.0 + felt!(1_u8)));
+
+    validator.reset().unwrap();
+
+    // The increment from the previous validation must be rolled back, while the same
+    // underlying (committed) state is still being read from.
+    assert_eq!(validator.get_nonce(sender_address).unwrap(), initial_nonce);
+}
+
+fn validator_without_balance(block_context: BlockContext) -> StatefulValidator<impl StateReader> {
+    let state = test_state(&block_context.chain_info, 0, &[(account_contract(), 1)]);
+    StatefulValidator::create(
+        state,
+        block_context,
+        Nonce(felt!(MAX_NONCE_FOR_VALIDATION_SKIP)),
+        ALLOW_FUTURE_NONCE_GAP,
+    )
+}
+
+#[rstest]
+fn fee_less_tx_does_not_require_balance(block_context: BlockContext) {
+    let mut validator = validator_without_balance(block_context);
+    let sender_address = account_contract().get_instance_address(0);
+    let nonce = validator.get_nonce(sender_address).unwrap();
+    let tx = account_invoke_tx(invoke_tx_args! { sender_address, nonce, max_fee: Fee(0) });
+
+    // `enforce_fee()` is false for a zero max-fee tx, so the balance check must not run even
+    // though the account has no funds.
+    let report = validator.perform_validations(tx, None, false, false, false).unwrap();
+
+    assert_eq!(report.validation_status, ValidationStatus::Ready);
+}
+
+#[rstest]
+fn skip_fee_check_overrides_a_fee_enforcing_tx(block_context: BlockContext) {
+    let mut validator = validator_without_balance(block_context);
+    let sender_address = account_contract().get_instance_address(0);
+    let nonce = validator.get_nonce(sender_address).unwrap();
+    let tx = account_invoke_tx(invoke_tx_args! {
+        sender_address,
+        nonce,
+        version: TransactionVersion::ONE,
+        max_fee: Fee(MAX_FEE),
+    });
+
+    // The tx enforces a (non-zero) fee, but `skip_fee_check` must bypass the balance check
+    // regardless, for Katana's fee-disabled mode.
+    let report = validator.perform_validations(tx, None, false, true, false).unwrap();
+
+    assert_eq!(report.validation_status, ValidationStatus::Ready);
+}